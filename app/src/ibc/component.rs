@@ -8,13 +8,16 @@ pub(crate) mod client;
 pub(crate) mod connection;
 pub(crate) mod state_key;
 
+use crate::ibc::component::client::StateReadExt as _;
 use crate::ibc::component::client::StateWriteExt as _;
+use crate::ibc::component::client::DEFAULT_OWN_CONSENSUS_STATE_RETENTION;
 use crate::ibc::ClientCounter;
 use crate::Component;
 use anyhow::Result;
 use async_trait::async_trait;
 use ibc::clients::ics07_tendermint::consensus_state::ConsensusState as TendermintConsensusState;
 use ibc::core::ics02_client::height::Height;
+use ibc::core::ics24_host::identifier::ChainId;
 use penumbra_chain::genesis;
 use penumbra_storage::StateWrite;
 use tendermint::abci;
@@ -24,10 +27,39 @@ pub struct IBCComponent {}
 
 #[async_trait]
 impl Component for IBCComponent {
-    #[instrument(name = "ibc", skip(state, _app_state))]
-    async fn init_chain<S: StateWrite>(mut state: S, _app_state: &genesis::AppState) {
+    #[instrument(name = "ibc", skip(state, app_state))]
+    async fn init_chain<S: StateWrite>(mut state: S, app_state: &genesis::AppState) {
         // set the initial client count
         state.put_client_counter(ClientCounter(0));
+
+        // Derive the revision number from the `chainID-N` suffix of the chain
+        // ID, per the convention used by Tendermint/ibc-rs `Height`s. A
+        // consensus-breaking upgrade's genesis carries a chain ID with a
+        // bumped `-N` suffix, and `init_chain` reruns against it, which is
+        // what actually advances the persisted number across upgrades; a
+        // non-upgrade restart reuses the same chain ID and so leaves it
+        // unchanged. Guard that invariant explicitly, since silently
+        // accepting a lower revision number here would let heights collide
+        // with ones already produced under the higher one.
+        let revision_number = ChainId::new(app_state.chain_params.chain_id.clone()).version();
+        let previous_revision_number = state
+            .get_revision_number()
+            .await
+            .expect("revision number read must not fail");
+        assert!(
+            revision_number >= previous_revision_number,
+            "chain ID {} encodes revision number {} lower than the already-persisted {}; \
+             a chain upgrade must never move the revision number backward",
+            app_state.chain_params.chain_id,
+            revision_number,
+            previous_revision_number
+        );
+        state.put_revision_number(revision_number);
+
+        // Record the default own-consensus-state retention explicitly on
+        // chain at genesis, rather than leaving it as an invisible Rust-level
+        // fallback, so that it's visible to and adjustable by governance.
+        state.put_own_consensus_state_retention(DEFAULT_OWN_CONSENSUS_STATE_RETENTION);
     }
 
     #[instrument(name = "ibc", skip(state, begin_block))]
@@ -35,7 +67,10 @@ impl Component for IBCComponent {
         // In BeginBlock, we want to save a copy of our consensus state to our
         // own state tree, so that when we get a message from our
         // counterparties, we can verify that they are committing the correct
-        // consensus states for us to their state tree.
+        // consensus states for us to their state tree. `put_penumbra_consensus_state`
+        // converts `header.time` via `ConsensusState::timestamp()` to compute
+        // its pruning cutoff, which is also where an unrepresentable block
+        // time is rejected — there's no need to validate it here too.
         let commitment_root: Vec<u8> = begin_block.header.app_hash.clone().into();
         let cs = TendermintConsensusState::new(
             commitment_root.into(),
@@ -43,13 +78,20 @@ impl Component for IBCComponent {
             begin_block.header.next_validators_hash,
         );
 
-        // Currently, we don't use a revision number, because we don't have
-        // any further namespacing of blocks than the block height.
-        let revision_number = 0;
+        let revision_number = state
+            .get_revision_number()
+            .await
+            .expect("revision number must be set in init_chain");
         let height = Height::new(revision_number, begin_block.header.height.into())
             .expect("block height cannot be zero");
 
-        state.put_penumbra_consensus_state(height, cs);
+        state
+            .put_penumbra_consensus_state(height, cs)
+            .await
+            .expect(
+                "block header time must be representable as a valid IBC timestamp, \
+                 and pruning our own consensus states should never otherwise fail",
+            );
     }
 
     #[instrument(name = "ibc", skip(_state, _end_block))]
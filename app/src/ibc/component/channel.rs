@@ -0,0 +1,90 @@
+use crate::ibc::component::state_key;
+use anyhow::{anyhow, Result};
+use ibc::core::ics04_channel::channel::ChannelEnd;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use penumbra_storage::{StateRead, StateWrite};
+
+#[async_trait::async_trait]
+pub trait StateReadExt: StateRead {
+    async fn get_channel(&self, port_id: &PortId, channel_id: &ChannelId) -> Result<ChannelEnd> {
+        self.get(&state_key::channel(port_id, channel_id))
+            .await?
+            .ok_or_else(|| anyhow!("channel not found for {}/{}", port_id, channel_id))
+    }
+
+    async fn get_packet_commitment(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: &Sequence,
+    ) -> Result<Vec<u8>> {
+        self.get(&state_key::packet_commitment(port_id, channel_id, sequence))
+            .await?
+            .ok_or_else(|| {
+                anyhow!(
+                    "packet commitment not found for {}/{}/{}",
+                    port_id,
+                    channel_id,
+                    sequence
+                )
+            })
+    }
+
+    async fn get_packet_acknowledgement(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: &Sequence,
+    ) -> Result<Vec<u8>> {
+        self.get(&state_key::packet_acknowledgement(
+            port_id, channel_id, sequence,
+        ))
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "packet acknowledgement not found for {}/{}/{}",
+                port_id,
+                channel_id,
+                sequence
+            )
+        })
+    }
+}
+
+impl<T: StateRead + ?Sized> StateReadExt for T {}
+
+#[async_trait::async_trait]
+pub trait StateWriteExt: StateWrite + StateReadExt {
+    fn put_channel(&mut self, port_id: &PortId, channel_id: &ChannelId, channel: ChannelEnd) {
+        self.put(state_key::channel(port_id, channel_id), channel);
+    }
+
+    fn put_packet_commitment(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: &Sequence,
+        commitment: Vec<u8>,
+    ) {
+        self.put(
+            state_key::packet_commitment(port_id, channel_id, sequence),
+            commitment,
+        );
+    }
+
+    fn put_packet_acknowledgement(
+        &mut self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: &Sequence,
+        acknowledgement: Vec<u8>,
+    ) {
+        self.put(
+            state_key::packet_acknowledgement(port_id, channel_id, sequence),
+            acknowledgement,
+        );
+    }
+}
+
+impl<T: StateWrite + ?Sized> StateWriteExt for T {}
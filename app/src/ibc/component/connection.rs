@@ -0,0 +1,25 @@
+use crate::ibc::component::state_key;
+use anyhow::{anyhow, Result};
+use ibc::core::ics03_connection::connection::ConnectionEnd;
+use ibc::core::ics24_host::identifier::ConnectionId;
+use penumbra_storage::{StateRead, StateWrite};
+
+#[async_trait::async_trait]
+pub trait StateReadExt: StateRead {
+    async fn get_connection(&self, connection_id: &ConnectionId) -> Result<ConnectionEnd> {
+        self.get(&state_key::connection(connection_id))
+            .await?
+            .ok_or_else(|| anyhow!("connection not found for {}", connection_id))
+    }
+}
+
+impl<T: StateRead + ?Sized> StateReadExt for T {}
+
+#[async_trait::async_trait]
+pub trait StateWriteExt: StateWrite + StateReadExt {
+    fn put_connection(&mut self, connection_id: &ConnectionId, connection: ConnectionEnd) {
+        self.put(state_key::connection(connection_id), connection);
+    }
+}
+
+impl<T: StateWrite + ?Sized> StateWriteExt for T {}
@@ -0,0 +1,99 @@
+//! Key space for IBC state stored in the Penumbra state tree.
+
+use ibc::core::ics02_client::height::Height;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+
+/// The number of clients this chain has created.
+pub fn client_counter() -> &'static str {
+    "ibc/ics02-client/client_counter"
+}
+
+/// The revision number of this chain, derived from its chain ID, used to
+/// namespace IBC heights across consensus-breaking upgrades and restarts.
+pub fn revision_number() -> &'static str {
+    "ibc/revision_number"
+}
+
+/// How long (in seconds) we retain our own historical consensus states for.
+/// See [`crate::ibc::component::client::DEFAULT_OWN_CONSENSUS_STATE_RETENTION`]
+/// for why this is governance-settable rather than a constant.
+pub fn own_consensus_state_retention_seconds() -> &'static str {
+    "ibc/ownConsensusStateRetentionSeconds"
+}
+
+/// The type of the client with the given `client_id` (e.g. `07-tendermint`).
+pub fn client_type(client_id: &ClientId) -> String {
+    format!("ibc/clients/{client_id}/clientType")
+}
+
+/// The `ClientState` of the client with the given `client_id`.
+pub fn client_state(client_id: &ClientId) -> String {
+    format!("ibc/clients/{client_id}/clientState")
+}
+
+/// The `ConsensusState` of the client with the given `client_id`, at `height`.
+pub fn consensus_state(client_id: &ClientId, height: &Height) -> String {
+    format!("ibc/clients/{client_id}/consensusStates/{height}")
+}
+
+/// Our own (Penumbra) consensus state, as recorded at `height`, so that
+/// counterparty clients tracking this chain can be proven against.
+pub fn penumbra_consensus_state(height: &Height) -> String {
+    format!("ibc/consensusStates/{height}")
+}
+
+/// An ordered `(revision_number, revision_height)` index over the consensus
+/// states stored for `client_id`, zero-padded so that lexicographic key
+/// order matches height order. This lets pruning do a bounded range scan
+/// from the lowest height instead of a full iteration.
+pub fn consensus_state_index_prefix(client_id: &ClientId) -> String {
+    format!("ibc/ics02-client/consensusStateIndex/{client_id}/")
+}
+
+pub fn consensus_state_index(client_id: &ClientId, height: &Height) -> String {
+    format!(
+        "{}{:020}-{:020}",
+        consensus_state_index_prefix(client_id),
+        height.revision_number(),
+        height.revision_height()
+    )
+}
+
+/// The equivalent ordered index for our own historical consensus states.
+pub fn penumbra_consensus_state_index_prefix() -> &'static str {
+    "ibc/consensusStateIndex/"
+}
+
+pub fn penumbra_consensus_state_index(height: &Height) -> String {
+    format!(
+        "{}{:020}-{:020}",
+        penumbra_consensus_state_index_prefix(),
+        height.revision_number(),
+        height.revision_height()
+    )
+}
+
+/// The `ConnectionEnd` with the given `connection_id`.
+pub fn connection(connection_id: &ConnectionId) -> String {
+    format!("ibc/ics03-connection/connections/{connection_id}")
+}
+
+/// The `ChannelEnd` for the given `(port_id, channel_id)`.
+pub fn channel(port_id: &PortId, channel_id: &ChannelId) -> String {
+    format!("ibc/ics04-channel/channelEnds/ports/{port_id}/channels/{channel_id}")
+}
+
+/// The packet commitment for `(port_id, channel_id, sequence)`.
+pub fn packet_commitment(port_id: &PortId, channel_id: &ChannelId, sequence: &Sequence) -> String {
+    format!("ibc/ics04-channel/commitments/ports/{port_id}/channels/{channel_id}/sequences/{sequence}")
+}
+
+/// The packet acknowledgement for `(port_id, channel_id, sequence)`.
+pub fn packet_acknowledgement(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: &Sequence,
+) -> String {
+    format!("ibc/ics04-channel/acks/ports/{port_id}/channels/{channel_id}/sequences/{sequence}")
+}
@@ -0,0 +1,11 @@
+pub mod component;
+pub mod query;
+
+pub use component::IBCComponent;
+
+/// The number of clients created by this chain.
+///
+/// Tracked so that freshly created clients can be assigned a unique,
+/// monotonically increasing [`ibc::core::ics24_host::identifier::ClientId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClientCounter(pub u64);
@@ -0,0 +1,149 @@
+//! Read-side IBC query logic, each returning an ICS-23 Merkle proof alongside
+//! the stored value so that an off-chain relayer can verify the response
+//! against the app hash without trusting the RPC node it queried.
+//!
+//! These are plain async functions over `&S: StateReadExt`, not a gRPC
+//! service: there is no generated `Query` server trait in this tree to
+//! implement against, so wiring them up to be reachable over gRPC (the
+//! request/response proto types, the service registration) is left to
+//! whichever crate owns that wiring. Each function here is the handler body
+//! such a service impl would delegate to.
+
+use crate::ibc::component::channel::StateReadExt as _;
+use crate::ibc::component::client::StateReadExt;
+use crate::ibc::component::connection::StateReadExt as _;
+use anyhow::{anyhow, Result};
+use ibc::clients::ics07_tendermint::client_state::ClientState as TendermintClientState;
+use ibc::clients::ics07_tendermint::consensus_state::ConsensusState as TendermintConsensusState;
+use ibc::core::ics02_client::height::Height;
+use ibc::core::ics03_connection::connection::ConnectionEnd;
+use ibc::core::ics04_channel::channel::ChannelEnd;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use ics23::CommitmentProof;
+use penumbra_storage::StateRead;
+
+/// A queried value, proven against the app hash at `proof_height`.
+pub struct Proven<T> {
+    pub value: T,
+    pub proof: CommitmentProof,
+    pub proof_height: Height,
+}
+
+/// The height at which the current app hash can be proven against, i.e. the
+/// most recent height we recorded our own consensus state for.
+async fn current_proof_height<S: StateReadExt>(state: &S) -> Result<Height> {
+    state
+        .retained_penumbra_consensus_heights()
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("no penumbra consensus state has been recorded yet"))
+}
+
+pub async fn client_state<S: StateReadExt>(
+    state: &S,
+    client_id: &ClientId,
+) -> Result<Proven<TendermintClientState>> {
+    let value = state.get_client_state(client_id).await?;
+    let (_, proof) = state
+        .get_with_proof(crate::ibc::component::state_key::client_state(client_id).into_bytes())
+        .await?;
+    Ok(Proven {
+        value,
+        proof,
+        proof_height: current_proof_height(state).await?,
+    })
+}
+
+pub async fn client_consensus_state<S: StateReadExt>(
+    state: &S,
+    client_id: &ClientId,
+    height: &Height,
+) -> Result<Proven<TendermintConsensusState>> {
+    let value = state.get_client_consensus_state(client_id, height).await?;
+    let (_, proof) = state
+        .get_with_proof(
+            crate::ibc::component::state_key::consensus_state(client_id, height).into_bytes(),
+        )
+        .await?;
+    Ok(Proven {
+        value,
+        proof,
+        proof_height: current_proof_height(state).await?,
+    })
+}
+
+pub async fn connection<S: crate::ibc::component::connection::StateReadExt + StateReadExt>(
+    state: &S,
+    connection_id: &ConnectionId,
+) -> Result<Proven<ConnectionEnd>> {
+    let value = state.get_connection(connection_id).await?;
+    let (_, proof) = state
+        .get_with_proof(crate::ibc::component::state_key::connection(connection_id).into_bytes())
+        .await?;
+    Ok(Proven {
+        value,
+        proof,
+        proof_height: current_proof_height(state).await?,
+    })
+}
+
+pub async fn channel<S: crate::ibc::component::channel::StateReadExt + StateReadExt>(
+    state: &S,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<Proven<ChannelEnd>> {
+    let value = state.get_channel(port_id, channel_id).await?;
+    let (_, proof) = state
+        .get_with_proof(crate::ibc::component::state_key::channel(port_id, channel_id).into_bytes())
+        .await?;
+    Ok(Proven {
+        value,
+        proof,
+        proof_height: current_proof_height(state).await?,
+    })
+}
+
+pub async fn packet_commitment<S: crate::ibc::component::channel::StateReadExt + StateReadExt>(
+    state: &S,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: &Sequence,
+) -> Result<Proven<Vec<u8>>> {
+    let value = state
+        .get_packet_commitment(port_id, channel_id, sequence)
+        .await?;
+    let (_, proof) = state
+        .get_with_proof(
+            crate::ibc::component::state_key::packet_commitment(port_id, channel_id, sequence)
+                .into_bytes(),
+        )
+        .await?;
+    Ok(Proven {
+        value,
+        proof,
+        proof_height: current_proof_height(state).await?,
+    })
+}
+
+pub async fn packet_acknowledgement<S: crate::ibc::component::channel::StateReadExt + StateReadExt>(
+    state: &S,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: &Sequence,
+) -> Result<Proven<Vec<u8>>> {
+    let value = state
+        .get_packet_acknowledgement(port_id, channel_id, sequence)
+        .await?;
+    let (_, proof) = state
+        .get_with_proof(
+            crate::ibc::component::state_key::packet_acknowledgement(port_id, channel_id, sequence)
+                .into_bytes(),
+        )
+        .await?;
+    Ok(Proven {
+        value,
+        proof,
+        proof_height: current_proof_height(state).await?,
+    })
+}